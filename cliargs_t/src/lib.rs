@@ -11,35 +11,176 @@ optional: log support
 
 use static_assertions::*;
 use std::collections::HashMap;
+use std::fmt;
 
 const FLAG_PREFIX: char = '-';
+const LONG_FLAG_PREFIX: &str = "--";
+
+///Represents every way that parsing or executing a command can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommanderError {
+    ///No command is registered under the given name.
+    CommandNotFound(String),
+    ///A flag was given more values than its cardinality (`Flag::multiple`/`Flag::max_values`) allows.
+    TooManyValues(String),
+    ///A flag that the command requires was not supplied.
+    MissingRequiredFlag(String),
+    ///The input did not contain a command name to parse.
+    ExpectedCommandName,
+    ///A value was found where a flag was expected.
+    UnexpectedValue(String),
+    ///A command's execution failed, such as a spawned child process exiting non-zero.
+    CommandFailed(String),
+    ///A flag's raw value didn't match its `ValueKind`, e.g. a non-numeric token for an `I64` flag
+    ///or a token outside of a `PossibleValues` set. Carries the flag's identifier and the offending input.
+    InvalidFlagValue(String, String),
+    ///A command's name or one of its aliases collides with an already-registered command or alias.
+    DuplicateCommandName(String),
+    ///A `FlagGroup` marked `required` had none of its members present. Carries the group's members, joined with ", ".
+    MissingRequiredFlagGroup(String),
+    ///A `FlagGroup` not marked `multiple` had more than one of its members present. Carries the conflicting members, joined with ", ".
+    ConflictingFlags(String),
+}
+
+impl fmt::Display for CommanderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommanderError::CommandNotFound(name) => write!(f, "Failed to find the target command: {}", name),
+            CommanderError::TooManyValues(flag) => write!(f, "Flag {} was given more values than it accepts", flag),
+            CommanderError::MissingRequiredFlag(flag) => write!(f, "Missing a required flag: {}", flag),
+            CommanderError::ExpectedCommandName => write!(f, "Expected a command name"),
+            CommanderError::UnexpectedValue(value) => write!(f, "Expected a flag, instead found: {}", value),
+            CommanderError::CommandFailed(reason) => write!(f, "Command failed: {}", reason),
+            CommanderError::InvalidFlagValue(flag, value) => write!(f, "Flag {} received an invalid value: {}", flag, value),
+            CommanderError::DuplicateCommandName(name) => write!(f, "{} is already registered as a command name or alias", name),
+            CommanderError::MissingRequiredFlagGroup(members) => write!(f, "At least one of the following flags is required: {}", members),
+            CommanderError::ConflictingFlags(members) => write!(f, "These flags cannot be used together: {}", members),
+        }
+    }
+}
+
+impl std::error::Error for CommanderError {}
+
+///Describes the type a flag's raw string value should be parsed into, and how to validate it.
+#[derive(Clone)]
+pub enum ValueKind {
+    ///The flag's value should parse as a `bool`. A bare flag with no token (e.g. `-v`) is treated as `true`.
+    Bool,
+    ///The flag's value should parse as an `i64`.
+    I64,
+    ///The flag's value should parse as a `u64`.
+    U64,
+    ///The flag's value should parse as an `f64`.
+    F64,
+    ///The flag's value is kept as-is.
+    String,
+    ///The flag's value must be one of the given strings.
+    PossibleValues(std::vec::Vec<&'static str>),
+}
+
+///A flag's value once it has been validated against its `ValueKind`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParsedValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    ///Every value collected for a flag declared with `Flag::multiple`.
+    List(std::vec::Vec<ParsedValue>),
+}
 
 /// Represents a flag for a command.
 #[derive(Clone)]
 pub struct Flag {
-    
+
     ///This identifier should be the letter or phrase that signifies the flag. This should not include '-'.
     ///This should also not include '-h', as that is reserved for displaying help information.
+    ///It is also the key this flag's value is stored under in the map handed to `Command::execute_command`.
     pub identifier: &'static str,
-    
+
     ///Help text for this flag.
     pub flag_help: &'static str,
 
     ///Whether or not this flag is required for the command to be used.
-    pub required: bool
+    pub required: bool,
+
+    ///The type this flag's raw value should be parsed and validated as.
+    pub kind: ValueKind,
+
+    ///Whether this flag can be given more than one value (e.g. `-f a.txt -f b.txt` or `-i x y z`).
+    pub multiple: bool,
+
+    ///An optional cap on how many values this flag accepts. `None` falls back to `1` for a flag that isn't
+    ///`multiple`, or unlimited for one that is.
+    pub max_values: Option<usize>,
+
+    ///The single-dash form this flag can be invoked under, e.g. `Some('c')` for `-c`.
+    pub short: Option<char>,
+
+    ///The double-dash form this flag can be invoked under, e.g. `Some("config")` for `--config`.
+    pub long: Option<&'static str>
+}
+
+///A constraint across a set of a command's flags, identified by their `Flag::identifier`s.
+#[derive(Clone)]
+pub struct FlagGroup {
+
+    ///The identifiers of the flags this group constrains.
+    pub members: std::vec::Vec<&'static str>,
+
+    ///Whether at least one member of this group must be present.
+    pub required: bool,
+
+    ///Whether more than one member of this group may be present at once. When `false`, supplying
+    ///two or more members is an error.
+    pub multiple: bool,
 }
 
 ///Implementors of this trait handle a specific command's execution.
 pub trait Command {
-    
-    ///The implementation of this function should execute the command with the given flag information.
-    fn execute_command(&self, flags: HashMap<String, String>);
+
+    ///The implementation of this function should execute the command with the given, already-typed flag values.
+    ///Returning an `Err` lets `Commander::handle_input` propagate the failure to the caller instead of swallowing it.
+    fn execute_command(&self, flags: HashMap<String, ParsedValue>) -> Result<(), CommanderError>;
 
     ///Returns general information about the command such as its name, help text, flags, and the flag's help information.
     fn get_information(&self) -> CommandInformation;
+
+    ///Commands that act as a parent in a subcommand tree (e.g. `tool group action`) override this to expose
+    ///their children, letting `Commander::handle_input` recursively dispatch into them. The default
+    ///implementation means this command has no children.
+    fn get_subcommands(&self) -> Option<&std::vec::Vec<Box<dyn Command>>> {
+        None
+    }
 }
 assert_obj_safe!(Command);
 
+///Waits on a spawned child process and maps a non-zero or missing exit status into a `CommanderError`.
+///Intended for commands that shell out and need to report the child's failure through normal control flow.
+pub fn wait_for_result(mut child: std::process::Child) -> Result<(), CommanderError> {
+    let status = child.wait().map_err(|error| CommanderError::CommandFailed(error.to_string()))?;
+
+    if status.success() {
+        Ok(())
+    }
+    else {
+        match status.code() {
+            Some(code) => Err(CommanderError::CommandFailed(format!("process exited with status code {}", code))),
+            None => Err(CommanderError::CommandFailed("process was terminated by a signal".to_string())),
+        }
+    }
+}
+
+///Identifies a shell to generate a tab-completion script for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
 ///Holds various information that is mainly utilized by the help command.
 #[derive(Clone)]
 pub struct CommandInformation {
@@ -52,6 +193,16 @@ pub struct CommandInformation {
 
     ///The flags that the command supports or requires
     pub flags: std::vec::Vec<Flag>,
+
+    ///Additional names this command can be invoked under, e.g. `rm` as an alias for `remove`.
+    pub aliases: std::vec::Vec<&'static str>,
+
+    ///Child commands this command dispatches to, e.g. `tool group action`. Used by `HelpCommand` to list
+    ///and descend into subcommands; actual dispatch is driven by `Command::get_subcommands`.
+    pub subcommands: std::vec::Vec<CommandInformation>,
+
+    ///Mutually-exclusive and/or required groupings across this command's flags, enforced by `Commander::verify_flags`.
+    pub flag_groups: std::vec::Vec<FlagGroup>,
 }
 pub struct HelpCommand {
     ///Some general information about a command
@@ -83,14 +234,27 @@ impl HelpCommand {
                     Flag {
                         identifier: "c",
                         flag_help: "Displays information about the specified command and its flags",
-                        required: false
+                        required: false,
+                        kind: ValueKind::String,
+                        multiple: false,
+                        max_values: None,
+                        short: Some('c'),
+                        long: Some("command")
                     },
                     Flag {
                         identifier: "f",
                         flag_help: "Displays information about a flag specific to the specified command",
-                        required: false
+                        required: false,
+                        kind: ValueKind::String,
+                        multiple: false,
+                        max_values: None,
+                        short: Some('f'),
+                        long: Some("flag")
                     }
-                ]
+                ],
+            aliases: vec![],
+            subcommands: vec![],
+            flag_groups: vec![]
         }
     }
 
@@ -106,11 +270,23 @@ impl HelpCommand {
         //Print a little header
         println!("'{}' help", command.command_name);
         println!("{}", command.command_help);
+        if !command.aliases.is_empty() {
+            println!("Aliases: {}", command.aliases.join(", "));
+        }
         println!("");
-        
+
         //Print the available flags
         for flag in command.flags {
-            println!("-{}, {}, required: {}", flag.identifier, flag.flag_help, flag.required);
+            println!("{}, {}, required: {}", HelpCommand::flag_forms(&flag), flag.flag_help, flag.required);
+        }
+
+        //Print the available subcommands, if any
+        if !command.subcommands.is_empty() {
+            println!("");
+            println!("Subcommands:");
+            for subcommand in command.subcommands {
+                println!("  {}, {}", subcommand.command_name, subcommand.command_help);
+            }
         }
     }
 
@@ -136,25 +312,57 @@ impl HelpCommand {
         return None;
     }
 
-    fn get_command_info(&self, command_name: &String) -> Option<CommandInformation> {
-        for command_info in self.known_commands.clone() {
-            if command_info.command_name == command_name {
-                return Some(command_info);
-            }
+    ///Formats a flag's short and/or long forms for display, e.g. `-c, --command`.
+    fn flag_forms(flag: &Flag) -> String {
+        let short_form = flag.short.map(|identifier| format!("-{}", identifier));
+        let long_form = flag.long.map(|identifier| format!("--{}", identifier));
+
+        match (short_form, long_form) {
+            (Some(short_form), Some(long_form)) => format!("{}, {}", short_form, long_form),
+            (Some(short_form), None) => short_form,
+            (None, Some(long_form)) => long_form,
+            (None, None) => format!("-{}", flag.identifier),
         }
-        return None;
+    }
+
+    ///Both of this command's flags are declared with `ValueKind::String`, so their parsed values are always strings.
+    fn as_string(value: &ParsedValue) -> Option<&String> {
+        match value {
+            ParsedValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    ///Resolves a command, descending into nested `subcommands` for each extra whitespace-separated segment in
+    ///`path` (e.g. `"parent child"` looks up `parent`, then `child` among its subcommands).
+    fn get_command_info(&self, path: &str) -> Option<CommandInformation> {
+        let mut segments = path.split_whitespace();
+        let first_segment = segments.next()?;
+
+        let mut current = self.known_commands.iter()
+            .find(|command_info| command_info.command_name == first_segment)?
+            .clone();
+
+        for segment in segments {
+            current = current.subcommands.iter()
+                .find(|subcommand_info| subcommand_info.command_name == segment)?
+                .clone();
+        }
+
+        return Some(current);
     }
 }
 
 impl Command for HelpCommand {
 
-    fn execute_command(&self, flags: std::collections::HashMap<std::string::String, std::string::String>) { 
-        let command = flags.get("c");
+    fn execute_command(&self, flags: std::collections::HashMap<std::string::String, ParsedValue>) -> Result<(), CommanderError> {
+        let command = flags.get("c").and_then(HelpCommand::as_string);
         if command.is_some() {
-            let command_info = self.get_command_info(command.unwrap());
+            let command_name = command.unwrap();
+            let command_info = self.get_command_info(command_name);
 
             if command_info.is_some() {
-                let flag = flags.get("f");
+                let flag = flags.get("f").and_then(HelpCommand::as_string);
                 if flag.is_some() {
                     //Display help for a specific command's flag
                     self.display_flag_help(command_info.unwrap(), flag.unwrap());
@@ -163,15 +371,16 @@ impl Command for HelpCommand {
                     //Display help about a specific command and list its flags and their help
                     self.display_command_help(command_info.unwrap());
                 }
-            } 
+            }
             else {
-                println!("{} is not a registered command", command.unwrap());
+                println!("{} is not a registered command", command_name);
             }
         }
         else {
             //Display help for all commands
             self.display_all_commands_help();
         }
+        return Ok(());
     }
 
     fn get_information(&self) -> CommandInformation { 
@@ -187,83 +396,279 @@ pub struct Commander<'a> {
 
 impl<'a> Commander<'a> {
 
-    pub fn new(commands: &'a mut std::vec::Vec<Box<dyn Command>>) -> Commander<'a> {
+    pub fn new(commands: &'a mut std::vec::Vec<Box<dyn Command>>) -> Result<Commander<'a>, CommanderError> {
         //Construct the help command and register it
         let help = HelpCommand::new(&commands);
         commands.insert(0, Box::new(help));
 
-        //Register the rest of the commands
+        //Register the rest of the commands, along with any aliases they declare
         let mut known: HashMap<String, &Box<dyn Command>> = HashMap::with_capacity(commands.len());
         for command in commands {
-            known.insert(command.get_information().command_name.to_string(), command);
+            let info = command.get_information();
+            let canonical_name = info.command_name.to_string();
+            if known.contains_key(&canonical_name) {
+                return Err(CommanderError::DuplicateCommandName(canonical_name));
+            }
+            known.insert(canonical_name, command);
+
+            for alias in info.aliases {
+                let alias_name = alias.to_string();
+                if known.contains_key(&alias_name) {
+                    return Err(CommanderError::DuplicateCommandName(alias_name));
+                }
+                known.insert(alias_name, command);
+            }
         }
-        
-        return Commander {
+
+        return Ok(Commander {
             known_commands: known
-        };
+        });
     }
 
-    /// Parses the specified tokens for flags and their values.
-    /// Returns the flags as a HashMap<String, String>
-    fn parse_flags(&self, tokens: std::str::SplitWhitespace) -> Option<HashMap<String, String>> {
-        let mut parsed_flags = HashMap::new();
-        let mut flag = String::new();
-        let mut flag_value;
-
-        for token in tokens {
-            if token.starts_with(FLAG_PREFIX) {
-                flag = token.to_string().replace("-", "");
-                if parsed_flags.contains_key(&flag) {
-                    //We shouldn't have a flag twice
-                    println!("Flag {} has been discovered twice", flag);
-                    return None;
-                } 
-                else {
-                    //Add the discovered flag
-                    parsed_flags.insert(flag.clone(), String::default());
+    ///Splits a raw input line into tokens on whitespace, treating a double-quoted run (e.g. `"parent child"`)
+    ///as a single token so multi-word values such as a subcommand path can be passed through one flag.
+    fn tokenize(input: &str) -> std::vec::Vec<String> {
+        let mut tokens = std::vec::Vec::new();
+        let mut current = String::new();
+        let mut has_current = false;
+        let mut in_quotes = false;
+
+        for character in input.trim().chars() {
+            if character == '"' {
+                in_quotes = !in_quotes;
+                has_current = true;
+            }
+            else if character.is_whitespace() && !in_quotes {
+                if has_current {
+                    tokens.push(std::mem::take(&mut current));
+                    has_current = false;
                 }
             }
             else {
-                flag_value = token.to_string();
-                let wrapped_stored_flag_value = parsed_flags.get_key_value(&flag);
-                if wrapped_stored_flag_value.is_some() {
-                    if wrapped_stored_flag_value.unwrap().1 == &String::new() {
-                        //Set the value for the flag
-                        parsed_flags.remove_entry(&flag);
-                        parsed_flags.insert(flag.clone(), flag_value);
+                current.push(character);
+                has_current = true;
+            }
+        }
+
+        if has_current {
+            tokens.push(current);
+        }
+
+        return tokens;
+    }
+
+    /// Parses the specified tokens for flags and their values.
+    /// A token starting with `--` is a long flag and may inline its first value with `--flag=value`; a token
+    /// starting with a single `-` is a short flag. Either way, the flag then greedily collects every following
+    /// token up to the next flag-prefixed token, so it may end up with one value, several, or none (a bare switch,
+    /// which is recorded as a single empty-string value). A flag that appears more than once (e.g. `-f a.txt -f b.txt`) has each occurrence's values
+    /// appended together; whether repetition is actually allowed for that flag is enforced later by
+    /// `verify_flags`, once its cardinality is known.
+    /// Returns the flags as a HashMap<String, Vec<String>>, keyed by whichever name the token was written with.
+    fn parse_flags(&self, tokens: &[String]) -> Result<HashMap<String, std::vec::Vec<String>>, CommanderError> {
+        let mut parsed_flags: HashMap<String, std::vec::Vec<String>> = HashMap::new();
+        let mut tokens = tokens.iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            if token.starts_with(LONG_FLAG_PREFIX) {
+                let body = &token[LONG_FLAG_PREFIX.len()..];
+                let (flag, inline_value) = match body.split_once('=') {
+                    Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                    None => (body.to_string(), None),
+                };
+                let mut values: std::vec::Vec<String> = inline_value.into_iter().collect();
+                while let Some(next_token) = tokens.peek() {
+                    if next_token.starts_with(FLAG_PREFIX) {
+                        break;
                     }
-                    else {
-                        //Flags shouldn't have two values
-                        println!("Flag {} already has a value", flag);
-                        return None;
+                    values.push(tokens.next().unwrap().clone());
+                }
+                //A bare occurrence (no following tokens) still needs to count towards this flag's total
+                //occurrences, so it gets a single empty-valued placeholder rather than vanishing once merged
+                //with any other occurrence.
+                if values.is_empty() {
+                    values.push(String::new());
+                }
+                //A flag may legitimately appear more than once (e.g. `multiple` flags given as repeated
+                //occurrences); whether that's actually allowed is checked later in verify_flags, where a
+                //flag's cardinality is known.
+                parsed_flags.entry(flag).or_insert_with(std::vec::Vec::new).extend(values);
+            }
+            else if token.starts_with(FLAG_PREFIX) {
+                let flag = token[FLAG_PREFIX.len_utf8()..].to_string();
+
+                let mut values = std::vec::Vec::new();
+                while let Some(next_token) = tokens.peek() {
+                    if next_token.starts_with(FLAG_PREFIX) {
+                        break;
                     }
+                    values.push(tokens.next().unwrap().clone());
+                }
+                //See the matching placeholder logic in the long-flag branch above: a bare occurrence still
+                //needs to count towards this flag's total occurrences.
+                if values.is_empty() {
+                    values.push(String::new());
+                }
+                parsed_flags.entry(flag).or_insert_with(std::vec::Vec::new).extend(values);
+            }
+            else {
+                return Err(CommanderError::UnexpectedValue(token.clone()));
+            }
+        }
+        return Ok(parsed_flags);
+    }
+
+    ///Validates a flag's raw token against its `ValueKind`, producing the typed value it represents.
+    fn parse_value(identifier: &str, kind: &ValueKind, raw_value: &str) -> Result<ParsedValue, CommanderError> {
+        match kind {
+            ValueKind::Bool => {
+                if raw_value.is_empty() {
+                    //A bare flag with no token is treated as a switch
+                    Ok(ParsedValue::Bool(true))
                 }
                 else {
-                    println!("Expected a flag, instead found: {}", flag_value);
+                    raw_value.parse::<bool>()
+                        .map(ParsedValue::Bool)
+                        .map_err(|_| CommanderError::InvalidFlagValue(identifier.to_string(), raw_value.to_string()))
+                }
+            },
+            ValueKind::I64 => raw_value.parse::<i64>()
+                .map(ParsedValue::I64)
+                .map_err(|_| CommanderError::InvalidFlagValue(identifier.to_string(), raw_value.to_string())),
+            ValueKind::U64 => raw_value.parse::<u64>()
+                .map(ParsedValue::U64)
+                .map_err(|_| CommanderError::InvalidFlagValue(identifier.to_string(), raw_value.to_string())),
+            ValueKind::F64 => raw_value.parse::<f64>()
+                .map(ParsedValue::F64)
+                .map_err(|_| CommanderError::InvalidFlagValue(identifier.to_string(), raw_value.to_string())),
+            ValueKind::String => Ok(ParsedValue::String(raw_value.to_string())),
+            ValueKind::PossibleValues(possible_values) => {
+                if possible_values.contains(&raw_value) {
+                    Ok(ParsedValue::String(raw_value.to_string()))
                 }
+                else {
+                    Err(CommanderError::InvalidFlagValue(identifier.to_string(), raw_value.to_string()))
+                }
+            },
+        }
+    }
+
+    ///Looks up a flag's raw values, merging together every form it was invoked under in this input: its
+    ///`identifier`, its `short` form, and its `long` form. A flag given under more than one form at once
+    ///(e.g. `-f a.txt --file b.txt`) has all of those values combined rather than only the first form found.
+    fn lookup_raw_values(parsed_flags: &HashMap<String, std::vec::Vec<String>>, flag: &Flag) -> Option<std::vec::Vec<String>> {
+        let mut combined = std::vec::Vec::new();
+        let mut found = false;
+
+        //Forms that coincide (e.g. an identifier equal to its short form) name the same input and are read once
+        let mut keys: std::vec::Vec<String> = std::vec::Vec::with_capacity(3);
+        let forms = std::iter::once(flag.identifier.to_string())
+            .chain(flag.short.map(|short| short.to_string()))
+            .chain(flag.long.map(|long| long.to_string()));
+        for key in forms {
+            if !keys.contains(&key) {
+                keys.push(key);
             }
         }
-        return Some(parsed_flags);
+        for key in &keys {
+            if let Some(values) = parsed_flags.get(key) {
+                found = true;
+                combined.extend(values.iter().cloned());
+            }
+        }
+
+        if found { Some(combined) } else { None }
+    }
+
+    ///Determines whether the provided flags meet a command's required flags, enforces each flag's cardinality,
+    ///validates its raw tokens against its `ValueKind`, enforces its `FlagGroup` constraints, and returns the
+    ///resulting typed map.
+    fn verify_flags(&self, parsed_flags: &HashMap<String, std::vec::Vec<String>>, information: &CommandInformation) -> Result<HashMap<String, ParsedValue>, CommanderError> {
+        let mut typed_flags = HashMap::with_capacity(information.flags.len());
+        for required_flag in &information.flags {
+            let raw_values = Commander::lookup_raw_values(parsed_flags, required_flag);
+            match raw_values {
+                Some(raw_values) => {
+                    //parse_flags always gives a present flag at least one value (a bare occurrence is
+                    //recorded as a single empty-valued placeholder), so raw_values is never empty here.
+                    let max_values = if required_flag.multiple {
+                        required_flag.max_values.unwrap_or(usize::MAX)
+                    }
+                    else {
+                        required_flag.max_values.unwrap_or(1)
+                    };
+                    if raw_values.len() > max_values {
+                        //Flags shouldn't have more values than their cardinality allows
+                        return Err(CommanderError::TooManyValues(required_flag.identifier.to_string()));
+                    }
+
+                    let mut values = std::vec::Vec::with_capacity(raw_values.len());
+                    for raw_value in &raw_values {
+                        values.push(Commander::parse_value(required_flag.identifier, &required_flag.kind, raw_value)?);
+                    }
+
+                    let parsed_value = if required_flag.multiple {
+                        ParsedValue::List(values)
+                    }
+                    else {
+                        values.remove(0)
+                    };
+                    typed_flags.insert(required_flag.identifier.to_string(), parsed_value);
+                },
+                None => {
+                    if required_flag.required {
+                        return Err(CommanderError::MissingRequiredFlag(required_flag.identifier.to_string()));
+                    }
+                },
+            }
+        }
+
+        for group in &information.flag_groups {
+            let present: std::vec::Vec<&'static str> = group.members.iter()
+                .filter(|member| typed_flags.contains_key(**member))
+                .map(|member| *member)
+                .collect();
+
+            if group.required && present.is_empty() {
+                return Err(CommanderError::MissingRequiredFlagGroup(group.members.join(", ")));
+            }
+
+            if !group.multiple && present.len() > 1 {
+                return Err(CommanderError::ConflictingFlags(present.join(", ")));
+            }
+        }
+
+        return Ok(typed_flags);
     }
 
-    ///Determines whether the provided flags meet a command's required flags and are valid.
-    fn verify_flags(&self, parsed_flags: &HashMap<String, String>, required_flags: std::vec::Vec<Flag>) -> bool {
-        for required_flag in required_flags {
-            if required_flag.required {
-                let had_flag = parsed_flags.contains_key(required_flag.identifier);
-                if !had_flag {
-                    println!("Missing a required flag: {}", required_flag.identifier);
-                    return false;
+    ///Executes `command` against the remaining tokens. If `command` declares subcommands and the next token
+    ///names one of them, dispatch descends into that child first; otherwise the tokens are parsed as this
+    ///command's own flags.
+    fn dispatch_command(&self, command: &Box<dyn Command>, tokens: &[String]) -> Result<(), CommanderError> {
+        if let Some(children) = command.get_subcommands() {
+            if let Some(next_token) = tokens.first() {
+                let next_name = next_token.trim().to_lowercase();
+                let child = children.iter().find(|child| {
+                    let info = child.get_information();
+                    info.command_name == next_name || info.aliases.contains(&next_name.as_str())
+                });
+
+                if let Some(child) = child {
+                    return self.dispatch_command(child, &tokens[1..]);
                 }
             }
         }
-        return true;
+
+        let raw_flags = self.parse_flags(tokens)?;
+        //We have our flags parsed, the command has been found and are ready to validate and execute
+        let typed_flags = self.verify_flags(&raw_flags, &command.get_information())?;
+        return command.execute_command(typed_flags);
     }
 
     ///Takes in a user's command input and parses and executes the command if everything is in order.
-    pub fn handle_input(&self, input: String) {
-        let mut tokens = input.trim().split_whitespace();
-        let command_name_wrapped = tokens.nth(0);
+    pub fn handle_input(&self, input: String) -> Result<(), CommanderError> {
+        let tokens = Commander::tokenize(&input);
+        let command_name_wrapped = tokens.first();
 
         if command_name_wrapped.is_some() {
             //Parse the command's name
@@ -273,21 +678,259 @@ impl<'a> Commander<'a> {
 
             if target_command.is_some() {
                 let command = target_command.unwrap();
-                let found_flags: Option<HashMap<String, String>> = self.parse_flags(tokens);
-                if found_flags.is_some() {
-                    //We have our flags parsed, the command has been found and are ready to execute the command
-                    let flags = found_flags.unwrap();
-                    if self.verify_flags(&flags, command.get_information().flags) {
-                        command.execute_command(flags);
-                    }
+                return self.dispatch_command(command, &tokens[1..]);
+            }
+            else {
+                return Err(CommanderError::CommandNotFound(command_name));
+            }
+        }
+        else {
+            return Err(CommanderError::ExpectedCommandName);
+        }
+    }
+
+    ///Generates a tab-completion script for the given shell, covering every registered command and its flags.
+    pub fn generate_completions(&self, shell: Shell) -> String {
+        let program_name = Commander::program_name();
+
+        //`known_commands` holds one entry per alias in addition to the canonical name, all pointing at the
+        //same command, so dedup by canonical name before handing commands to the generators below.
+        let mut by_canonical_name: HashMap<&'static str, CommandInformation> = HashMap::new();
+        for command in self.known_commands.values() {
+            let info = command.get_information();
+            by_canonical_name.entry(info.command_name).or_insert(info);
+        }
+        let mut commands: std::vec::Vec<CommandInformation> = by_canonical_name.into_values().collect();
+        commands.sort_by_key(|info| info.command_name);
+
+        match shell {
+            Shell::Bash => Commander::generate_bash_completions(&program_name, &commands),
+            Shell::Zsh => Commander::generate_zsh_completions(&program_name, &commands),
+            Shell::Fish => Commander::generate_fish_completions(&program_name, &commands),
+            Shell::PowerShell => Commander::generate_powershell_completions(&program_name, &commands),
+        }
+    }
+
+    ///Determines the name completions should be registered under, falling back to "cli" when the running
+    ///executable's name can't be determined.
+    fn program_name() -> String {
+        std::env::current_exe().ok()
+            .and_then(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "cli".to_string())
+    }
+
+    ///Every first-token name a command can be completed as: its canonical `command_name` followed by its aliases.
+    fn command_completion_names(info: &CommandInformation) -> std::vec::Vec<&'static str> {
+        let mut names = std::vec::Vec::with_capacity(info.aliases.len() + 1);
+        names.push(info.command_name);
+        names.extend(info.aliases.iter().copied());
+        return names;
+    }
+
+    ///The dash-prefixed forms a flag can be completed as, e.g. `["-c", "--command"]`.
+    fn flag_completion_tokens(flag: &Flag) -> std::vec::Vec<String> {
+        let mut tokens = std::vec::Vec::new();
+        if let Some(short) = flag.short {
+            tokens.push(format!("-{}", short));
+        }
+        if let Some(long) = flag.long {
+            tokens.push(format!("--{}", long));
+        }
+        if tokens.is_empty() {
+            tokens.push(format!("-{}", flag.identifier));
+        }
+        return tokens;
+    }
+
+    ///Builds the `case` arm for `info` at word position `depth` (its name is expected in `COMP_WORDS[depth]`),
+    ///recursing into `info.subcommands` at `depth + 1` so nested subcommands are completable too.
+    fn bash_case_arm(depth: usize, info: &CommandInformation) -> String {
+        let body = if info.subcommands.is_empty() {
+            let flags: std::vec::Vec<String> = info.flags.iter().flat_map(Commander::flag_completion_tokens).collect();
+            format!("COMPREPLY=( $(compgen -W \"{}\" -- \"$cur\") )\n", flags.join(" "))
+        }
+        else {
+            let next_depth = depth + 1;
+            let child_names: std::vec::Vec<&str> = info.subcommands.iter().flat_map(Commander::command_completion_names).collect();
+            let mut child_cases = String::new();
+            for child in &info.subcommands {
+                child_cases.push_str(&Commander::bash_case_arm(next_depth, child));
+            }
+            format!(
+                "if [ $COMP_CWORD -eq {next_depth} ]; then\n                COMPREPLY=( $(compgen -W \"{children}\" -- \"$cur\") )\n                return 0\n            fi\n            case \"${{COMP_WORDS[{next_depth}]}}\" in\n{child_cases}            esac\n",
+                next_depth = next_depth, children = child_names.join(" "), child_cases = child_cases
+            )
+        };
+
+        return format!(
+            "        {names})\n            {body}            ;;\n",
+            names = Commander::command_completion_names(info).join("|"), body = body
+        );
+    }
+
+    fn generate_bash_completions(program_name: &str, commands: &std::vec::Vec<CommandInformation>) -> String {
+        let mut command_names: std::vec::Vec<&str> = std::vec::Vec::new();
+        let mut cases = String::new();
+        for info in commands {
+            command_names.extend(Commander::command_completion_names(info));
+            cases.push_str(&Commander::bash_case_arm(1, info));
+        }
+
+        format!(
+            "_{program_name}_completions()\n{{\n    local cur\n    COMPREPLY=()\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n\n    if [ $COMP_CWORD -eq 1 ]; then\n        COMPREPLY=( $(compgen -W \"{commands}\" -- \"$cur\") )\n        return 0\n    fi\n\n    case \"${{COMP_WORDS[1]}}\" in\n{cases}    esac\n}}\ncomplete -F _{program_name}_completions {program_name}\n",
+            program_name = program_name,
+            commands = command_names.join(" "),
+            cases = cases
+        )
+    }
+
+    ///Builds the `case` arm for `info` matched against `$line[depth]`, recursing into `info.subcommands` at
+    ///`depth + 1` so nested subcommands are completable too.
+    fn zsh_case_arm(depth: usize, info: &CommandInformation) -> String {
+        let body = if info.subcommands.is_empty() {
+            let mut flag_descriptions = String::new();
+            for flag in &info.flags {
+                for token in Commander::flag_completion_tokens(flag) {
+                    flag_descriptions.push_str(&format!("                '{}[{}]'\n", token, flag.flag_help));
                 }
             }
+            format!("_arguments \\\n{}\n", flag_descriptions.trim_end())
+        }
+        else {
+            let next_depth = depth + 1;
+            let mut child_cases = String::new();
+            for child in &info.subcommands {
+                child_cases.push_str(&Commander::zsh_case_arm(next_depth, child));
+            }
+            format!("case $line[{next_depth}] in\n{child_cases}                esac\n", next_depth = next_depth, child_cases = child_cases)
+        };
+
+        return format!(
+            "            {names})\n                {body}                ;;\n",
+            names = Commander::command_completion_names(info).join("|"), body = body
+        );
+    }
+
+    fn generate_zsh_completions(program_name: &str, commands: &std::vec::Vec<CommandInformation>) -> String {
+        let mut command_descriptions = String::new();
+        let mut cases = String::new();
+        for info in commands {
+            for name in Commander::command_completion_names(info) {
+                command_descriptions.push_str(&format!("        '{}:{}'\n", name, info.command_help));
+            }
+
+            cases.push_str(&Commander::zsh_case_arm(1, info));
+        }
+
+        format!(
+            "#compdef {program_name}\n\n_{program_name}()\n{{\n    local line\n\n    _arguments -C \\\n        '1: :->command' \\\n        '*::arg:->args'\n\n    case $state in\n        command)\n            local -a commands\n            commands=(\n{descriptions}            )\n            _describe 'command' commands\n            ;;\n        args)\n            case $line[1] in\n{cases}            esac\n            ;;\n    esac\n}}\n\ncompdef _{program_name} {program_name}\n",
+            program_name = program_name,
+            descriptions = command_descriptions,
+            cases = cases
+        )
+    }
+
+    ///Builds the `complete` lines for `info`, reached via `ancestors` (the names of its parent commands, in
+    ///order), recursing into `info.subcommands` so nested subcommands are completable too.
+    fn fish_completion_lines(program_name: &str, ancestors: &std::vec::Vec<&'static str>, info: &CommandInformation) -> String {
+        let mut lines = String::new();
+        let names = Commander::command_completion_names(info);
+
+        let naming_condition = if ancestors.is_empty() {
+            "__fish_use_subcommand".to_string()
+        }
+        else {
+            format!("__fish_seen_subcommand_from {}", ancestors.join(" "))
+        };
+        for name in &names {
+            lines.push_str(&format!(
+                "complete -c {program_name} -n \"{naming_condition}\" -a \"{name}\" -d \"{command_help}\"\n",
+                program_name = program_name, naming_condition = naming_condition, name = name, command_help = info.command_help
+            ));
+        }
+
+        //Any of this command's names counts as "seen" for the purposes of completing its flags or descending
+        //into its subcommands.
+        let seen_condition = format!("__fish_seen_subcommand_from {}", names.join(" "));
+        for flag in &info.flags {
+            let short_option = flag.short.map(|short| format!(" -s {}", short)).unwrap_or_default();
+            let long_option = flag.long.map(|long| format!(" -l {}", long)).unwrap_or_default();
+            if short_option.is_empty() && long_option.is_empty() {
+                lines.push_str(&format!(
+                    "complete -c {program_name} -n \"{seen_condition}\" -s {identifier} -d \"{flag_help}\"\n",
+                    program_name = program_name, seen_condition = seen_condition, identifier = flag.identifier, flag_help = flag.flag_help
+                ));
+            }
             else {
-                println!("Failed to find the target command: {}", command_name);
+                lines.push_str(&format!(
+                    "complete -c {program_name} -n \"{seen_condition}\"{short_option}{long_option} -d \"{flag_help}\"\n",
+                    program_name = program_name, seen_condition = seen_condition, short_option = short_option, long_option = long_option, flag_help = flag.flag_help
+                ));
             }
         }
+
+        let mut child_ancestors = ancestors.clone();
+        child_ancestors.extend(names.iter().copied());
+        for child in &info.subcommands {
+            lines.push_str(&Commander::fish_completion_lines(program_name, &child_ancestors, child));
+        }
+
+        return lines;
+    }
+
+    fn generate_fish_completions(program_name: &str, commands: &std::vec::Vec<CommandInformation>) -> String {
+        let mut lines = String::new();
+        for info in commands {
+            lines.push_str(&Commander::fish_completion_lines(program_name, &std::vec::Vec::new(), info));
+        }
+        lines
+    }
+
+    ///Builds the `switch` arm for `info`, matched against `$tokens[{token_index}].Value`, recursing into
+    ///`info.subcommands` at `token_index + 1` so nested subcommands are completable too.
+    fn powershell_case_arm(token_index: usize, info: &CommandInformation) -> String {
+        let names: std::vec::Vec<String> = Commander::command_completion_names(info).iter().map(|name| format!("'{}'", name)).collect();
+
+        let body = if info.subcommands.is_empty() {
+            let flags: std::vec::Vec<String> = info.flags.iter().flat_map(Commander::flag_completion_tokens).map(|token| format!("'{}'", token)).collect();
+            format!("@({})", flags.join(", "))
+        }
         else {
-            println!("Expected a command name");
+            let next_index = token_index + 1;
+            let child_names: std::vec::Vec<String> = info.subcommands.iter()
+                .flat_map(Commander::command_completion_names)
+                .map(|name| format!("'{}'", name))
+                .collect();
+            let mut child_cases = String::new();
+            for child in &info.subcommands {
+                child_cases.push_str(&Commander::powershell_case_arm(next_index, child));
+            }
+            format!(
+                "if ($tokens.Count -le {next_index}) {{ @({child_names}) }} else {{ switch ($tokens[{next_index}].Value) {{\n{child_cases}            default {{ @() }}\n        }} }}",
+                next_index = next_index, child_names = child_names.join(", "), child_cases = child_cases
+            )
+        };
+
+        let mut arms = String::new();
+        for name in &names {
+            arms.push_str(&format!("        {name} {{ {body} }}\n", name = name, body = body));
         }
+        return arms;
+    }
+
+    fn generate_powershell_completions(program_name: &str, commands: &std::vec::Vec<CommandInformation>) -> String {
+        let mut command_names: std::vec::Vec<String> = std::vec::Vec::new();
+        let mut command_cases = String::new();
+        for info in commands {
+            command_names.extend(Commander::command_completion_names(info).iter().map(|name| format!("'{}'", name)));
+            command_cases.push_str(&Commander::powershell_case_arm(0, info));
+        }
+
+        format!(
+            "Register-ArgumentCompleter -Native -CommandName {program_name} -ScriptBlock {{\n    param($wordToComplete, $commandAst, $cursorPosition)\n\n    $commands = @({commands})\n\n    $tokens = $commandAst.CommandElements | Select-Object -Skip 1\n    if ($tokens.Count -le 1) {{\n        $commands | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{ [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_) }}\n        return\n    }}\n\n    $candidates = switch ($tokens[0].Value) {{\n{cases}        default {{ @() }}\n    }}\n    $candidates | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{ [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_) }}\n}}\n",
+            program_name = program_name,
+            commands = command_names.join(", "),
+            cases = command_cases
+        )
     }
 }
\ No newline at end of file